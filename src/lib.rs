@@ -0,0 +1,419 @@
+use aws_lc_rs::hmac;
+use zeroize::Zeroize;
+
+// Wraps the underlying HMAC algorithm so HKDF-Extract/Expand are generic over
+// the hash function, the same way aws-lc-rs/ring wrap `hkdf::Algorithm`
+// around `hmac::Algorithm` instead of hardcoding one digest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Algorithm(hmac::Algorithm);
+
+pub const HKDF_SHA1_FOR_LEGACY_USE_ONLY: Algorithm = Algorithm(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY);
+pub const HKDF_SHA256: Algorithm = Algorithm(hmac::HMAC_SHA256);
+pub const HKDF_SHA384: Algorithm = Algorithm(hmac::HMAC_SHA384);
+pub const HKDF_SHA512: Algorithm = Algorithm(hmac::HMAC_SHA512);
+
+impl Algorithm {
+    // HashLen: the output length (in octets) of the underlying hash function.
+    pub fn len(&self) -> usize {
+        self.0.digest_algorithm().output_len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn hmac_algorithm(&self) -> hmac::Algorithm {
+        self.0
+    }
+}
+
+pub struct Ikm {
+    pub data: Vec<u8>,
+}
+
+pub struct Prk {
+    algorithm: Algorithm,
+    data: Vec<u8>,
+}
+
+// Redacts `data` so the PRK itself never ends up in a log line or test
+// failure message.
+impl core::fmt::Debug for Prk {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Prk")
+            .field("algorithm", &self.algorithm)
+            .field("data", &"[redacted]")
+            .finish()
+    }
+}
+
+// The PRK is secret material derived from IKM/salt (or imported directly via
+// `from_prk`); wipe it on drop so it doesn't linger in a freed allocation.
+impl Drop for Prk {
+    fn drop(&mut self) {
+        self.data.zeroize();
+    }
+}
+
+// Describes the length (in octets) of a typed OKM, the way ring's `KeyType`
+// lets a caller request output sized for a specific downstream primitive
+// instead of a bare `usize`.
+pub trait OkmLength {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl OkmLength for usize {
+    fn len(&self) -> usize {
+        *self
+    }
+}
+
+// A 256-bit AES key.
+pub struct Aes256GcmKey;
+impl OkmLength for Aes256GcmKey {
+    fn len(&self) -> usize {
+        32
+    }
+}
+
+// A 96-bit AEAD nonce.
+pub struct AeadNonce;
+impl OkmLength for AeadNonce {
+    fn len(&self) -> usize {
+        12
+    }
+}
+
+// Output keying material, tagged with the length (and therefore intended
+// use) it was expanded for. Expanding with a typed `L` instead of a bare
+// `usize` documents intent at the type level and stops callers from
+// accidentally requesting the wrong number of bytes for a downstream
+// primitive.
+pub struct Okm<L: OkmLength> {
+    len: L,
+    data: Vec<u8>,
+}
+
+// OKM is the derived secret key material itself; wipe it on drop for the
+// same reason as `Prk`.
+impl<L: OkmLength> Drop for Okm<L> {
+    fn drop(&mut self) {
+        self.data.zeroize();
+    }
+}
+
+impl<L: OkmLength> Okm<L> {
+    pub fn len(&self) -> usize {
+        self.len.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Copies the OKM into `out`, consuming self. `out` must be exactly
+    // `self.len()` bytes.
+    pub fn fill(self, out: &mut [u8]) -> Result<(), Error> {
+        if out.len() != self.data.len() {
+            return Err(Error::InvalidLength);
+        }
+        out.copy_from_slice(&self.data);
+        Ok(())
+    }
+}
+
+impl From<Okm<Aes256GcmKey>> for [u8; 32] {
+    fn from(okm: Okm<Aes256GcmKey>) -> Self {
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&okm.data);
+        out
+    }
+}
+
+impl From<Okm<AeadNonce>> for [u8; 12] {
+    fn from(okm: Okm<AeadNonce>) -> Self {
+        let mut out = [0u8; 12];
+        out.copy_from_slice(&okm.data);
+        out
+    }
+}
+
+// Errors returned when deriving keys from attacker- or caller-supplied
+// parameters (output length, imported PRK) rather than a crate invariant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    // The requested output length is greater than 255 * HashLen.
+    InvalidLength,
+    // The supplied PRK is shorter than one HashLen, so it cannot have come
+    // from a valid HKDF-Extract.
+    InvalidPrkLength,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::InvalidLength => write!(f, "requested output length exceeds 255 * HashLen"),
+            Error::InvalidPrkLength => write!(f, "supplied PRK is shorter than HashLen"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// HKDF-Extract(salt, IKM) -> PRK
+//
+// Inputs:
+//   salt     optional salt value (a non-secret random value);
+//        if not provided, it is set to a string of HashLen zeros.
+//   IKM      input keying material
+//
+// Output:
+//   PRK      a pseudorandom key (of HashLen octets)
+//
+// The output PRK is calculated as follows:
+//   PRK = HMAC-Hash(salt, IKM)/
+pub fn extract(algorithm: Algorithm, salt: &[u8], ikm: &Ikm) -> Prk {
+    let key = hmac::Key::new(algorithm.hmac_algorithm(), salt);
+    let tag = hmac::sign(&key, &ikm.data);
+    Prk {
+        algorithm,
+        data: tag.as_ref().to_vec(),
+    }
+}
+
+// Convenience for the common case where IKM is empty and the salt itself
+// is the secret (e.g. deriving from a pre-shared key used as salt).
+pub fn extract_from_zero_ikm(algorithm: Algorithm, salt: &[u8]) -> Prk {
+    extract(algorithm, salt, &Ikm { data: vec![] })
+}
+
+// Imports an already-computed pseudorandom key, skipping the extract step
+// entirely. For integrating with systems that hand over a PRK out-of-band
+// rather than an IKM/salt pair. `prk` must be at least `algorithm.len()`
+// bytes, matching the RFC 5869 requirement that PRK be at least HashLen
+// octets.
+pub fn from_prk(algorithm: Algorithm, prk: &[u8]) -> Result<Prk, Error> {
+    if prk.len() < algorithm.len() {
+        return Err(Error::InvalidPrkLength);
+    }
+    Ok(Prk {
+        algorithm,
+        data: prk.to_vec(),
+    })
+}
+
+// HKDF-Expand(PRK, info, L) -> OKM
+// Options:
+//   Hash   a hash function; HashLen denotes the length of the
+//          hash function output in octets
+//
+// Inputs:
+//   PRK    a pseudorandom key of at least HashLen octets
+//          (usually, the output from the extract step)
+//   info   optional context and application specific information
+//          (can be a zero-length string)
+//   L      length of output keying material in octets
+//          (<= 255*HashLen)
+//
+// Output:
+//   OKM      output keying material (of L octets)
+//
+// The output OKM is calculated as follows:
+//    N = ceil(L/HashLen)
+//    T = T(1) | T(2) | T(3) | ... | T(N)
+//    OKM = first L octets of T
+//
+//    where:
+//    T(0) = empty string (zero length)
+//    T(1) = HMAC-Hash(PRK, T(0) | info | 0x01)
+//    T(2) = HMAC-Hash(PRK, T(1) | info | 0x02)
+//    T(3) = HMAC-Hash(PRK, T(2) | info | 0x03)
+//    ...
+//
+// (where the constant concatenated to the end of each T(n) is a single octet.)
+//
+// This is a thin, allocating wrapper over `expand_multi_info`, which goes
+// through `expand_multi_info_typed` to `expand_multi_info_into`.
+pub fn expand(prk: &Prk, info: &[u8], len: usize) -> Result<Okm<usize>, Error> {
+    expand_multi_info(prk, &[info], len)
+}
+
+// Same as `expand`, but `info` is given as several segments that are fed
+// into each per-block HMAC in order, without the caller having to
+// concatenate them into one buffer first. Protocols like TLS 1.3's
+// HkdfLabel or Noise build `info` out of multiple pieces, so the HMAC input
+// for `T(i)` becomes `prev_t || info[0] || info[1] || ... || [i]`.
+pub fn expand_multi_info(prk: &Prk, info: &[&[u8]], len: usize) -> Result<Okm<usize>, Error> {
+    expand_multi_info_typed(prk, info, len)
+}
+
+// Same as `expand_multi_info`, but tags the returned OKM with a typed
+// length `L` (e.g. `Aes256GcmKey`) instead of a bare `usize`, so the caller
+// can convert it directly into the downstream key type via `fill`/`into`.
+pub fn expand_multi_info_typed<L: OkmLength>(
+    prk: &Prk,
+    info: &[&[u8]],
+    len: L,
+) -> Result<Okm<L>, Error> {
+    let mut data = vec![0u8; len.len()];
+    expand_multi_info_into(prk, info, &mut data)?;
+    Ok(Okm { len, data })
+}
+
+// Same as `expand`, but writes OKM directly into a caller-provided buffer
+// instead of allocating a `Vec`. `out.len()` is the requested output length
+// `L`; each `T(i)` is computed on the fly and only the bytes the caller
+// actually asked for are copied out, so nothing beyond `out.len()` is ever
+// materialized.
+pub fn expand_into(prk: &Prk, info: &[u8], out: &mut [u8]) -> Result<(), Error> {
+    expand_multi_info_into(prk, &[info], out)
+}
+
+// Combines `expand_multi_info` and `expand_into`: multiple `info` segments,
+// written directly into a caller-provided buffer one `HashLen` chunk at a
+// time.
+pub fn expand_multi_info_into(prk: &Prk, info: &[&[u8]], out: &mut [u8]) -> Result<(), Error> {
+    let hash_len = prk.algorithm.len();
+    if prk.data.len() < hash_len {
+        return Err(Error::InvalidPrkLength);
+    }
+
+    let n: u64 = (out.len() as f64 / hash_len as f64).ceil() as u64;
+    if n > u8::MAX as u64 {
+        return Err(Error::InvalidLength);
+    }
+    let n = n as u8;
+
+    // T(0) = empty string (zero length)
+    let mut prev_t: Vec<u8> = vec![];
+    for (i, chunk) in (1..=n).zip(out.chunks_mut(hash_len)) {
+        let key = hmac::Key::new(prk.algorithm.hmac_algorithm(), prk.data.as_ref());
+        let mut ctx = hmac::Context::with_key(&key);
+        ctx.update(&prev_t);
+        for segment in info {
+            ctx.update(segment);
+        }
+        ctx.update(&[i]);
+        let t = ctx.sign();
+
+        chunk.copy_from_slice(&t.as_ref()[..chunk.len()]);
+        prev_t = t.as_ref().to_vec();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 5869 Appendix A.1, Test Case 1 (HKDF-SHA-256, basic test case).
+    const RFC5869_TC1_IKM: [u8; 22] = [0x0b; 22];
+    const RFC5869_TC1_SALT: [u8; 13] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+    ];
+    const RFC5869_TC1_INFO: [u8; 10] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+    const RFC5869_TC1_OKM: [u8; 42] = [
+        0x3c, 0xb2, 0x5f, 0x25, 0xfa, 0xac, 0xd5, 0x7a, 0x90, 0x43, 0x4f, 0x64, 0xd0, 0x36, 0x2f,
+        0x2a, 0x2d, 0x2d, 0x0a, 0x90, 0xcf, 0x1a, 0x5a, 0x4c, 0x5d, 0xb0, 0x2d, 0x56, 0xec, 0xc4,
+        0xc5, 0xbf, 0x34, 0x00, 0x72, 0x08, 0xd5, 0xb8, 0x87, 0x18, 0x58, 0x65,
+    ];
+
+    #[test]
+    fn extract_then_expand_matches_rfc5869_test_case_1() {
+        let ikm = Ikm {
+            data: RFC5869_TC1_IKM.to_vec(),
+        };
+        let prk = extract(HKDF_SHA256, &RFC5869_TC1_SALT, &ikm);
+        let okm = expand(&prk, &RFC5869_TC1_INFO, RFC5869_TC1_OKM.len()).unwrap();
+
+        let mut out = vec![0u8; RFC5869_TC1_OKM.len()];
+        okm.fill(&mut out).unwrap();
+        assert_eq!(out, RFC5869_TC1_OKM);
+    }
+
+    #[test]
+    fn extract_from_zero_ikm_matches_extract_with_empty_ikm() {
+        let from_zero = extract_from_zero_ikm(HKDF_SHA256, &RFC5869_TC1_SALT);
+        let from_empty = extract(HKDF_SHA256, &RFC5869_TC1_SALT, &Ikm { data: vec![] });
+        assert_eq!(from_zero.data, from_empty.data);
+    }
+
+    #[test]
+    fn from_prk_rejects_a_too_short_prk() {
+        let short_prk = vec![0u8; HKDF_SHA256.len() - 1];
+        assert_eq!(
+            from_prk(HKDF_SHA256, &short_prk).unwrap_err(),
+            Error::InvalidPrkLength
+        );
+    }
+
+    #[test]
+    fn from_prk_accepts_an_exact_length_prk_and_expands_like_extract() {
+        let ikm = Ikm {
+            data: RFC5869_TC1_IKM.to_vec(),
+        };
+        let extracted = extract(HKDF_SHA256, &RFC5869_TC1_SALT, &ikm);
+        let imported = from_prk(HKDF_SHA256, &extracted.data).unwrap();
+
+        let okm = expand(&imported, &RFC5869_TC1_INFO, RFC5869_TC1_OKM.len()).unwrap();
+        let mut out = vec![0u8; RFC5869_TC1_OKM.len()];
+        okm.fill(&mut out).unwrap();
+        assert_eq!(out, RFC5869_TC1_OKM);
+    }
+
+    #[test]
+    fn typed_okm_expands_to_the_right_size_and_converts_via_into() {
+        let ikm = Ikm {
+            data: RFC5869_TC1_IKM.to_vec(),
+        };
+        let prk = extract(HKDF_SHA256, &RFC5869_TC1_SALT, &ikm);
+
+        let key_okm =
+            expand_multi_info_typed(&prk, &[&RFC5869_TC1_INFO[..]], Aes256GcmKey).unwrap();
+        assert_eq!(key_okm.len(), 32);
+        let key: [u8; 32] = key_okm.into();
+
+        let nonce_okm = expand_multi_info_typed(&prk, &[&RFC5869_TC1_INFO[..]], AeadNonce).unwrap();
+        assert_eq!(nonce_okm.len(), 12);
+        let nonce: [u8; 12] = nonce_okm.into();
+
+        // A 32-byte key and a 12-byte nonce expanded from the same PRK/info
+        // are just the first N bytes of the same HKDF-Expand output stream.
+        let mut expected_okm = vec![0u8; 32];
+        expand_into(&prk, &RFC5869_TC1_INFO, &mut expected_okm).unwrap();
+        assert_eq!(&key[..], expected_okm.as_slice());
+        assert_eq!(&nonce[..], &expected_okm[..12]);
+    }
+
+    #[test]
+    fn expand_into_matches_allocating_expand_for_full_and_partial_blocks() {
+        let ikm = Ikm {
+            data: RFC5869_TC1_IKM.to_vec(),
+        };
+        let prk = extract(HKDF_SHA256, &RFC5869_TC1_SALT, &ikm);
+
+        // 64 == 2 * HashLen: every block is full, no truncation of T(N).
+        let full_len = 2 * HKDF_SHA256.len();
+        // 50 is not a multiple of HashLen: the last block is truncated.
+        let partial_len = 50;
+
+        for len in [full_len, partial_len] {
+            let allocated = {
+                let okm = expand(&prk, &RFC5869_TC1_INFO, len).unwrap();
+                let mut out = vec![0u8; len];
+                okm.fill(&mut out).unwrap();
+                out
+            };
+
+            let mut into_out = vec![0u8; len];
+            expand_into(&prk, &RFC5869_TC1_INFO, &mut into_out).unwrap();
+
+            assert_eq!(into_out, allocated, "mismatch for len = {len}");
+        }
+    }
+}